@@ -1,6 +1,7 @@
 mod sub;
 use clap::Parser;
 use std::ffi::OsString;
+use std::path::Path;
 use std::process;
 use sub::{Input, Sub};
 
@@ -19,6 +20,18 @@ struct Cli {
     in_place: bool,
     #[arg(short, long, help = "Only match the pattern on whole words")]
     whole_word: bool,
+    #[arg(
+        short = 'F',
+        long = "fixed-strings",
+        help = "Treat the pattern as a literal string instead of a regex, and `$` in the replacement literally (escape sequences like \\n are still interpreted)"
+    )]
+    fixed_strings: bool,
+    #[arg(
+        short = 'M',
+        long,
+        help = "Match across the whole input instead of line by line, so patterns can span newlines (ignores --match)"
+    )]
+    multiline: bool,
     #[arg(
         short = 'm',
         long = "match",
@@ -26,6 +39,25 @@ struct Cli {
         help = "Only substitute on lines that match the pattern"
     )]
     line_match: Option<String>,
+    #[arg(
+        short,
+        long,
+        help = "Descend into directory arguments and substitute in every regular file found"
+    )]
+    recursive: bool,
+    #[arg(
+        long,
+        requires = "recursive",
+        value_name = "pattern",
+        help = "Only visit files under a recursive directory whose name matches this glob"
+    )]
+    glob: Option<String>,
+    #[arg(
+        short = 'n',
+        long,
+        help = "Show a colorized diff of the substitution without writing anything, so a run can be audited before using --in-place"
+    )]
+    preview: bool,
 }
 
 fn main() {
@@ -33,7 +65,16 @@ fn main() {
     let inputs = if cli.files.is_empty() {
         vec![Input::StdIn]
     } else {
-        cli.files.iter().map(|f| Input::File(f)).collect()
+        cli.files
+            .iter()
+            .map(|f| {
+                if cli.recursive && Path::new(f).is_dir() {
+                    Input::Directory(f)
+                } else {
+                    Input::File(f)
+                }
+            })
+            .collect()
     };
     let sub = Sub::init(&cli, inputs);
     let result = sub.run(atty::is(atty::Stream::Stdout));
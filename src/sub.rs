@@ -1,5 +1,5 @@
 use core::fmt;
-use regex::RegexBuilder;
+use regex::bytes::{Regex, RegexBuilder};
 use std::borrow::Cow;
 use std::ffi::{OsStr, OsString};
 use std::fs::{self, File};
@@ -13,26 +13,36 @@ pub struct Sub<'a> {
     pub in_place: bool,
     pub whole_word: bool,
     pub ignore_case: bool,
+    pub is_literal: bool,
+    pub multiline: bool,
     pub match_pattern: Option<&'a str>,
+    pub glob: Option<&'a str>,
+    pub preview: bool,
     pub inputs: Vec<Input<'a>>,
 }
 
 #[derive(Debug)]
 pub enum SubError {
     FailedToWrite,
-    InvalidUTF8,
+    FailedToRead,
     RegexError,
     FileNotFoundError(OsString),
     CanNotCreateTempFile,
     CanNotReadPermissions(OsString),
     CanNotSetPermissions(OsString),
     CanNotReplaceInPlace(OsString, io::Error),
+    InvalidReplaceCapture(String),
+    CanNotReadDirectory(OsString, io::Error),
+    CanNotSyncTempFile(OsString, io::Error),
+    RecursiveRequiresInPlaceOrPreview,
+    IsADirectory(OsString),
 }
 
 #[derive(Debug, Clone)]
 pub enum Input<'a> {
     StdIn,
     File(&'a OsStr),
+    Directory(&'a OsStr),
 }
 
 impl fmt::Display for SubError {
@@ -41,7 +51,7 @@ impl fmt::Display for SubError {
 
         match self {
             FailedToWrite => write!(f, "Output stream has been closed"),
-            InvalidUTF8 => write!(f, "Input contains invalid UTF-8"),
+            FailedToRead => write!(f, "Failed to read from input"),
             RegexError => write!(f, "Regex error"),
             FileNotFoundError(path) => write!(f, "Can not open file '{}'", path.to_string_lossy()),
             CanNotCreateTempFile => write!(f, "Can not create temp file"),
@@ -61,12 +71,54 @@ impl fmt::Display for SubError {
                 path.to_string_lossy(),
                 error
             ),
+            InvalidReplaceCapture(name) => write!(
+                f,
+                "Replacement references capture group '{}', which does not exist in the pattern",
+                name
+            ),
+            CanNotReadDirectory(path, error) => write!(
+                f,
+                "Can not read directory '{}' with error '{}'",
+                path.to_string_lossy(),
+                error
+            ),
+            CanNotSyncTempFile(path, error) => write!(
+                f,
+                "Can not sync temp file for '{}' with error '{}'",
+                path.to_string_lossy(),
+                error
+            ),
+            RecursiveRequiresInPlaceOrPreview => write!(
+                f,
+                "--recursive requires --in-place or --preview, otherwise substituted \
+                 content would be dumped to stdout for every file without changing any of them"
+            ),
+            IsADirectory(path) => write!(
+                f,
+                "'{}' is a directory, pass --recursive to descend into it",
+                path.to_string_lossy()
+            ),
         }
     }
 }
 
 type Result<T> = std::result::Result<T, SubError>;
 
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// The compiled pattern, optional line filter, and unescaped replacement
+/// bytes that every substitution call needs, bundled so `replace` and
+/// `replace_directory` don't take each one as a separate parameter.
+#[derive(Clone, Copy)]
+struct ReplaceSpec<'a> {
+    re: &'a Regex,
+    line_match_pattern: &'a Option<Regex>,
+    replacement: &'a [u8],
+}
+
 impl<'a> Sub<'a> {
     pub fn init(cli: &'a Cli, inputs: Vec<Input<'a>>) -> Sub<'a> {
         Sub {
@@ -75,66 +127,104 @@ impl<'a> Sub<'a> {
             in_place: cli.in_place,
             whole_word: cli.whole_word,
             ignore_case: cli.ignore_case,
+            is_literal: cli.fixed_strings,
+            multiline: cli.multiline,
             match_pattern: cli.line_match.as_deref(),
+            glob: cli.glob.as_deref(),
+            preview: cli.preview,
             inputs,
         }
     }
 
     pub fn run(&self, is_tty: bool) -> Result<()> {
-        let pattern = if self.whole_word {
-            format!(r"\b{}\b", self.pattern)
+        if !self.in_place
+            && !self.preview
+            && self.inputs.iter().any(|i| matches!(i, Input::Directory(_)))
+        {
+            return Err(SubError::RecursiveRequiresInPlaceOrPreview);
+        }
+
+        let pattern = if self.is_literal {
+            regex::escape(self.pattern)
         } else {
             self.pattern.to_string()
         };
+        let pattern = if self.whole_word {
+            format!(r"\b{}\b", pattern)
+        } else {
+            pattern
+        };
         let stdin = io::stdin();
         let stdout = io::stdout();
         let re = RegexBuilder::new(&pattern)
             .case_insensitive(self.ignore_case)
+            .multi_line(self.multiline)
+            .dot_matches_new_line(self.multiline)
             .build()
             .map_err(|_| SubError::RegexError)?;
 
         let line_match_pattern = self
             .match_pattern
             .map(|p| {
-                RegexBuilder::new(&p)
+                RegexBuilder::new(p)
                     .case_insensitive(self.ignore_case)
                     .build()
                     .map_err(|_| SubError::RegexError)
             })
             .transpose()?;
+
+        if !self.is_literal {
+            validate_replace_captures(self.replacement, &re)?;
+        }
+        let replacement = unescape(self.replacement);
+
+        let glob_pattern = self
+            .glob
+            .map(|g| regex::Regex::new(&glob_to_regex(g)).map_err(|_| SubError::RegexError))
+            .transpose()?;
+
+        let spec = ReplaceSpec {
+            re: &re,
+            line_match_pattern: &line_match_pattern,
+            replacement: &replacement,
+        };
+
         for input in self.inputs.iter() {
+            if let Input::Directory(dir) = input {
+                self.replace_directory(&spec, glob_pattern.as_ref(), dir, &stdin, &stdout, is_tty);
+                continue;
+            }
+
+            if let Input::File(path) = input {
+                if std::path::Path::new(path).is_dir() {
+                    return Err(SubError::IsADirectory(path.to_os_string()));
+                }
+            }
+
+            let label = input_label(input);
+
+            if self.preview {
+                let mut reader = create_reader(input, &stdin)?;
+                let mut writer = io::BufWriter::new(stdout.lock());
+                self.replace(&spec, &mut reader, &mut writer, &label, is_tty)?;
+                continue;
+            }
+
             if is_tty {
                 let mut reader = create_reader(input, &stdin)?;
                 let mut output = stdout.lock();
-                self.replace(&re, &line_match_pattern, &mut reader, &mut output)?;
+                self.replace(&spec, &mut reader, &mut output, &label, is_tty)?;
             } else {
                 if let Input::File(path) = input {
-                    let mut reader = create_reader(input, &stdin)?;
                     if self.in_place {
-                        let temp_file = tempfile::Builder::new()
-                            .prefix("sub_")
-                            .tempfile()
-                            .map_err(|_| SubError::CanNotCreateTempFile)?;
-
-                        let mut writer = io::BufWriter::new(&temp_file);
-                        self.replace(&re, &line_match_pattern, &mut reader, &mut writer)?;
-
-                        let current_file_permissions = fs::metadata(path)
-                            .map_err(|_| SubError::CanNotReadPermissions(path.to_os_string()))?
-                            .permissions();
-
-                        fs::set_permissions(temp_file.path(), current_file_permissions)
-                            .map_err(|_| SubError::CanNotSetPermissions(temp_file.path().into()))?;
-
-                        fs::copy(temp_file.path(), &path)
-                            .map_err(|e| SubError::CanNotReplaceInPlace(path.to_os_string(), e))?;
+                        self.replace_in_place(&spec, path)?;
                     } else {
                         unreachable!();
                     }
                 } else {
                     let mut reader = create_reader(input, &stdin)?;
                     let mut writer = io::BufWriter::new(stdout.lock());
-                    self.replace(&re, &line_match_pattern, &mut reader, &mut writer)?;
+                    self.replace(&spec, &mut reader, &mut writer, &label, is_tty)?;
                 }
             }
         }
@@ -142,34 +232,424 @@ impl<'a> Sub<'a> {
         Ok(())
     }
 
+    /// Performs the substitution, writing the result to `writer`. Returns
+    /// whether any substitution actually occurred, so callers that rewrite a
+    /// file in place can skip the rewrite entirely when nothing changed.
+    ///
+    /// When `self.preview` is set, nothing is passed through; instead a
+    /// unified-diff-style preview of the changed lines (headed by `label`) is
+    /// written to `writer`, colorized with ANSI escapes when `is_tty`.
     fn replace(
         &self,
-        re: &regex::Regex,
-        line_match_pattern: &Option<regex::Regex>,
+        spec: &ReplaceSpec,
         reader: &mut dyn BufRead,
         writer: &mut dyn Write,
-    ) -> Result<()> {
-        let mut line_buffer = String::new();
+        label: &str,
+        is_tty: bool,
+    ) -> Result<bool> {
+        let ReplaceSpec {
+            re,
+            line_match_pattern,
+            replacement,
+        } = *spec;
+
+        if self.multiline {
+            let mut buffer = Vec::new();
+            reader
+                .read_to_end(&mut buffer)
+                .map_err(|_| SubError::FailedToRead)?;
+            let new_content = if self.is_literal {
+                re.replace_all(&buffer, regex::bytes::NoExpand(replacement))
+            } else {
+                re.replace_all(&buffer, replacement)
+            };
+            let changed = matches!(new_content, Cow::Owned(_));
+            if self.preview {
+                if changed {
+                    print_preview_lines(
+                        writer,
+                        re,
+                        &buffer,
+                        replacement,
+                        self.is_literal,
+                        label,
+                        is_tty,
+                    )?;
+                }
+                return Ok(changed);
+            }
+            writer
+                .write_all(&new_content)
+                .map_err(|_| SubError::FailedToWrite)?;
+            return Ok(changed);
+        }
+
+        let mut changed = false;
+        let mut line_buffer: Vec<u8> = Vec::new();
+        let mut line_no = 0usize;
+        let mut header_printed = false;
         loop {
             line_buffer.clear();
             let num_bytes = reader
-                .read_line(&mut line_buffer)
-                .map_err(|_| SubError::InvalidUTF8)?;
+                .read_until(b'\n', &mut line_buffer)
+                .map_err(|_| SubError::FailedToRead)?;
             if num_bytes == 0 {
                 break;
             }
-            let new_line = if line_match_pattern
+            line_no += 1;
+            let line_matches = line_match_pattern
                 .as_ref()
-                .map_or(true, |m| m.is_match(&line_buffer))
-            {
-                re.replace_all(&line_buffer, self.replacement)
+                .map_or(true, |m| m.is_match(&line_buffer));
+
+            if self.preview {
+                let diff = line_matches
+                    .then(|| self.diff_line(re, strip_newline(&line_buffer), replacement, is_tty))
+                    .flatten();
+                if let Some((old_line, new_line)) = diff {
+                    changed = true;
+                    if !header_printed {
+                        write_out(writer, format!("--- {}\n", label).as_bytes())?;
+                        header_printed = true;
+                    }
+                    write_out(writer, format!("@@ line {} @@\n", line_no).as_bytes())?;
+                    write_out(writer, b"-")?;
+                    write_out(writer, &old_line)?;
+                    write_out(writer, b"\n+")?;
+                    write_out(writer, &new_line)?;
+                    write_out(writer, b"\n")?;
+                }
+                continue;
+            }
+
+            let new_line = if line_matches {
+                if self.is_literal {
+                    re.replace_all(&line_buffer, regex::bytes::NoExpand(replacement))
+                } else {
+                    re.replace_all(&line_buffer, replacement)
+                }
             } else {
                 Cow::from(&line_buffer)
             };
-            write!(writer, "{}", new_line).map_err(|_| SubError::FailedToWrite)?;
+            if matches!(new_line, Cow::Owned(_)) {
+                changed = true;
+            }
+            writer
+                .write_all(&new_line)
+                .map_err(|_| SubError::FailedToWrite)?;
         }
-        Ok(())
+        Ok(changed)
+    }
+
+    /// Builds the colorized old/new halves of a preview diff line for a
+    /// single matching line, highlighting only the matched span (and its
+    /// replacement) rather than the whole line. Returns `None` when `line`
+    /// does not actually match `re`.
+    fn diff_line(
+        &self,
+        re: &Regex,
+        line: &[u8],
+        replacement: &[u8],
+        colorize: bool,
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let mut old_out = Vec::new();
+        let mut new_out = Vec::new();
+        let mut last = 0;
+        let mut any_match = false;
+
+        for caps in re.captures_iter(line) {
+            let m = caps.get(0).expect("capture 0 always matches");
+            any_match = true;
+            old_out.extend_from_slice(&line[last..m.start()]);
+            new_out.extend_from_slice(&line[last..m.start()]);
+            push_colored(&mut old_out, &line[m.start()..m.end()], ANSI_RED, colorize);
+            let expanded = if self.is_literal {
+                replacement.to_vec()
+            } else {
+                let mut dst = Vec::new();
+                caps.expand(replacement, &mut dst);
+                dst
+            };
+            push_colored(&mut new_out, &expanded, ANSI_GREEN, colorize);
+            last = m.end();
+        }
+
+        if !any_match {
+            return None;
+        }
+        old_out.extend_from_slice(&line[last..]);
+        new_out.extend_from_slice(&line[last..]);
+        Some((old_out, new_out))
     }
+
+    /// Recursively walks `dir`, substituting in every regular file found.
+    /// A file (or the directory walk itself) that can't be read is reported
+    /// to stderr and skipped, so one bad entry does not abort the whole run.
+    /// `run` guarantees `self.in_place` or `self.preview` is set before this
+    /// is called, so a directory argument always ends in a real write or a
+    /// preview, never an unlabelled dump of substituted content to stdout.
+    fn replace_directory(
+        &self,
+        spec: &ReplaceSpec,
+        glob_pattern: Option<&regex::Regex>,
+        dir: &OsStr,
+        stdin: &io::Stdin,
+        stdout: &io::Stdout,
+        is_tty: bool,
+    ) {
+        let mut paths = Vec::new();
+        let mut errors = Vec::new();
+        collect_files(dir, glob_pattern, &mut paths, &mut errors);
+        for error in &errors {
+            eprintln!("[sub error]: {}", error);
+        }
+
+        let mut modified = 0usize;
+        for path in &paths {
+            let label = path.to_string_lossy().into_owned();
+            let outcome = if self.preview {
+                create_reader(&Input::File(path), stdin).and_then(|mut reader| {
+                    let mut writer = io::BufWriter::new(stdout.lock());
+                    self.replace(spec, &mut reader, &mut writer, &label, is_tty)
+                })
+            } else {
+                self.replace_in_place(spec, path)
+            };
+            match outcome {
+                Ok(changed) => {
+                    if changed {
+                        modified += 1;
+                    }
+                }
+                Err(e) => eprintln!("[sub error]: {}: {}", path.to_string_lossy(), e),
+            }
+        }
+
+        let verb = if self.preview { "would modify" } else { "modified" };
+        eprintln!(
+            "sub: {} {} of {} file(s) under '{}'",
+            verb,
+            modified,
+            paths.len(),
+            dir.to_string_lossy()
+        );
+    }
+
+    /// Rewrites `path` in place. The replacement is written to a temp file
+    /// created alongside `path` (same directory, so the final `fs::rename`
+    /// stays on one filesystem and is atomic on POSIX: readers either see the
+    /// old file or the new one, never a partially-written one). When nothing
+    /// actually changed the temp file is discarded and `path` is left
+    /// untouched, so unmodified files keep their original mtime. Returns
+    /// whether the file was actually rewritten.
+    fn replace_in_place(&self, spec: &ReplaceSpec, path: &OsStr) -> Result<bool> {
+        let stdin = io::stdin();
+        let mut reader = create_reader(&Input::File(path), &stdin)?;
+
+        // Resolve `path` to the file it actually refers to. If `path` is a
+        // symlink, the temp file needs to live next to (and the final
+        // `fs::rename` needs to land on) the real target, not the symlink
+        // itself -- otherwise the rename would replace the symlink with a
+        // plain file, leaving the file it pointed at (and any other
+        // hardlinks to it) untouched.
+        let real_path = fs::canonicalize(path).unwrap_or_else(|_| std::path::PathBuf::from(path));
+
+        let dir = real_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| std::path::Path::new("."));
+        let temp_file = tempfile::Builder::new()
+            .prefix("sub_")
+            .tempfile_in(dir)
+            .map_err(|_| SubError::CanNotCreateTempFile)?;
+
+        let changed = {
+            let mut writer = io::BufWriter::new(&temp_file);
+            let changed = self.replace(spec, &mut reader, &mut writer, "", false)?;
+            writer
+                .flush()
+                .map_err(|e| SubError::CanNotSyncTempFile(path.to_os_string(), e))?;
+            changed
+        };
+
+        if !changed {
+            return Ok(false);
+        }
+
+        let current_file_metadata = fs::metadata(&real_path)
+            .map_err(|_| SubError::CanNotReadPermissions(path.to_os_string()))?;
+
+        fs::set_permissions(temp_file.path(), current_file_metadata.permissions())
+            .map_err(|_| SubError::CanNotSetPermissions(temp_file.path().into()))?;
+        copy_ownership(&current_file_metadata, temp_file.path());
+
+        temp_file
+            .as_file()
+            .sync_all()
+            .map_err(|e| SubError::CanNotSyncTempFile(path.to_os_string(), e))?;
+
+        fs::rename(temp_file.path(), &real_path)
+            .map_err(|e| SubError::CanNotReplaceInPlace(path.to_os_string(), e))?;
+
+        Ok(true)
+    }
+}
+
+/// Interprets `\n`, `\t`, `\r`, `\0` and `\\` escape sequences in a replacement
+/// string into their byte values, leaving everything else (including `$`
+/// capture references) untouched.
+fn unescape(s: &str) -> Vec<u8> {
+    let mut result = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            result.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push(b'\n'),
+            Some('t') => result.push(b'\t'),
+            Some('r') => result.push(b'\r'),
+            Some('0') => result.push(0),
+            Some('\\') => result.push(b'\\'),
+            Some(other) => {
+                result.push(b'\\');
+                let mut buf = [0u8; 4];
+                result.extend_from_slice(other.encode_utf8(&mut buf).as_bytes());
+            }
+            None => result.push(b'\\'),
+        }
+    }
+    result
+}
+
+/// Scans a replacement string for `$N`, `${N}` and `${name}` capture
+/// references and ensures each one names a group that actually exists in
+/// `re`, so typos fail fast instead of silently expanding to an empty string.
+/// A doubled `$$` is `Captures::expand`'s escape for a literal `$` and is
+/// skipped rather than treated as a reference, mirroring its runtime semantics.
+fn validate_replace_captures(replacement: &str, re: &Regex) -> Result<()> {
+    let capture_ref = regex::Regex::new(r"\$\$|\$(?:\{([^}]+)\}|([0-9A-Za-z_]+))").unwrap();
+    for caps in capture_ref.captures_iter(replacement) {
+        let name = match caps.get(1).or_else(|| caps.get(2)) {
+            Some(m) => m.as_str(),
+            None => continue,
+        };
+        let is_valid = match name.parse::<usize>() {
+            Ok(n) => n < re.captures_len(),
+            Err(_) => re.capture_names().any(|cn| cn == Some(name)),
+        };
+        if !is_valid {
+            return Err(SubError::InvalidReplaceCapture(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// A human-readable name for an input, used as the `---` header in preview
+/// output.
+fn input_label(input: &Input<'_>) -> String {
+    match input {
+        Input::StdIn => "(standard input)".to_string(),
+        Input::File(path) => path.to_string_lossy().into_owned(),
+        Input::Directory(dir) => dir.to_string_lossy().into_owned(),
+    }
+}
+
+/// Strips a single trailing `\n` or `\r\n` from a line read with
+/// `read_until(b'\n', ..)`, so preview output doesn't show a stray blank line.
+fn strip_newline(line: &[u8]) -> &[u8] {
+    if let Some(stripped) = line.strip_suffix(b"\r\n") {
+        stripped
+    } else if let Some(stripped) = line.strip_suffix(b"\n") {
+        stripped
+    } else {
+        line
+    }
+}
+
+fn write_out(writer: &mut dyn Write, bytes: &[u8]) -> Result<()> {
+    writer.write_all(bytes).map_err(|_| SubError::FailedToWrite)
+}
+
+/// Best-effort restoration of the owning user/group from `metadata` onto
+/// `target`. Only root (or a user holding `CAP_CHOWN`) can actually change
+/// ownership, so a failure here is swallowed rather than propagated: losing
+/// ownership on an in-place edit that otherwise already succeeded shouldn't
+/// turn into a hard error for the common unprivileged case.
+#[cfg(unix)]
+fn copy_ownership(metadata: &fs::Metadata, target: &std::path::Path) {
+    use std::os::unix::fs::{chown, MetadataExt};
+    let _ = chown(target, Some(metadata.uid()), Some(metadata.gid()));
+}
+
+#[cfg(not(unix))]
+fn copy_ownership(_metadata: &fs::Metadata, _target: &std::path::Path) {}
+
+/// Appends `text` to `buf`, wrapped in bold `color` ANSI escapes when
+/// `colorize` is set.
+fn push_colored(buf: &mut Vec<u8>, text: &[u8], color: &str, colorize: bool) {
+    if colorize {
+        buf.extend_from_slice(color.as_bytes());
+        buf.extend_from_slice(ANSI_BOLD.as_bytes());
+    }
+    buf.extend_from_slice(text);
+    if colorize {
+        buf.extend_from_slice(ANSI_RESET.as_bytes());
+    }
+}
+
+/// Prints a preview diff for every match in `buffer`, used by `--multiline`
+/// mode where a match can span (or add/remove) newlines. Each hunk is
+/// anchored to the byte offset of the match itself -- like `diff_line` does
+/// for the single-line case -- rather than re-aligning `old`/`new` by split
+/// line index, which would desync every following line once a replacement
+/// changes the line count.
+fn print_preview_lines(
+    writer: &mut dyn Write,
+    re: &Regex,
+    buffer: &[u8],
+    replacement: &[u8],
+    is_literal: bool,
+    label: &str,
+    colorize: bool,
+) -> Result<()> {
+    let mut header_printed = false;
+
+    for caps in re.captures_iter(buffer) {
+        let m = caps.get(0).expect("capture 0 always matches");
+        let line_no = buffer[..m.start()].iter().filter(|&&b| b == b'\n').count() + 1;
+        let expanded = if is_literal {
+            replacement.to_vec()
+        } else {
+            let mut dst = Vec::new();
+            caps.expand(replacement, &mut dst);
+            dst
+        };
+
+        if !header_printed {
+            write_out(writer, format!("--- {}\n", label).as_bytes())?;
+            header_printed = true;
+        }
+        write_out(writer, format!("@@ line {} @@\n", line_no).as_bytes())?;
+
+        for old_line in m.as_bytes().split(|&b| b == b'\n') {
+            let mut colored = Vec::new();
+            push_colored(&mut colored, old_line, ANSI_RED, colorize);
+            write_out(writer, b"-")?;
+            write_out(writer, &colored)?;
+            write_out(writer, b"\n")?;
+        }
+
+        for new_line in expanded.split(|&b| b == b'\n') {
+            let mut colored = Vec::new();
+            push_colored(&mut colored, new_line, ANSI_GREEN, colorize);
+            write_out(writer, b"+")?;
+            write_out(writer, &colored)?;
+            write_out(writer, b"\n")?;
+        }
+    }
+    Ok(())
 }
 
 fn create_reader(input: &Input<'_>, stdin: &io::Stdin) -> Result<Box<dyn BufRead>> {
@@ -179,6 +659,80 @@ fn create_reader(input: &Input<'_>, stdin: &io::Stdin) -> Result<Box<dyn BufRead
             let f = File::open(path).map_err(|_| SubError::FileNotFoundError(path.into()))?;
             Box::new(BufReader::new(f))
         }
+        Input::Directory(_) => unreachable!("directories are expanded before being read"),
     };
     Ok(reader)
 }
+
+/// Recursively collects every regular file under `dir` into `files`,
+/// skipping symlinked directories to avoid traversal cycles. A glob, when
+/// given, is matched against each file's base name. Entries that can't be
+/// read are pushed onto `errors` instead of aborting the walk.
+fn collect_files(
+    dir: &OsStr,
+    glob_pattern: Option<&regex::Regex>,
+    files: &mut Vec<OsString>,
+    errors: &mut Vec<SubError>,
+) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(SubError::CanNotReadDirectory(dir.to_os_string(), e));
+            return;
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(SubError::CanNotReadDirectory(dir.to_os_string(), e));
+                continue;
+            }
+        };
+        let path = entry.path();
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => {
+                errors.push(SubError::CanNotReadDirectory(path.into_os_string(), e));
+                continue;
+            }
+        };
+
+        if file_type.is_symlink() {
+            if let Ok(target) = fs::metadata(&path) {
+                if target.is_file() && glob_matches(glob_pattern, &path) {
+                    files.push(path.into_os_string());
+                }
+            }
+        } else if file_type.is_dir() {
+            collect_files(path.as_os_str(), glob_pattern, files, errors);
+        } else if file_type.is_file() && glob_matches(glob_pattern, &path) {
+            files.push(path.into_os_string());
+        }
+    }
+}
+
+fn glob_matches(glob_pattern: Option<&regex::Regex>, path: &std::path::Path) -> bool {
+    match glob_pattern {
+        None => true,
+        Some(re) => path
+            .file_name()
+            .is_some_and(|name| re.is_match(&name.to_string_lossy())),
+    }
+}
+
+/// Translates a simple shell glob (`*` and `?` wildcards, everything else
+/// literal) into an anchored regex.
+fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
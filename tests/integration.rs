@@ -80,3 +80,224 @@ fn regex_replacement() {
         .for_input("foo dummy foo\r\nfoo\r\n")
         .expect_output("x x x\r\nx\r\n");
 }
+
+#[test]
+fn fixed_strings_treats_pattern_literally() {
+    ReplacementTest::new("a.b", "X")
+        .arg("-F")
+        .for_input("a.b axb\n")
+        .expect_output("X axb\n");
+}
+
+#[test]
+fn in_place_rewrites_file_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("f.txt");
+    std::fs::write(&path, "foo\n").unwrap();
+
+    sub()
+        .arg("-p")
+        .arg("foo")
+        .arg("bar")
+        .arg(&path)
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "bar\n");
+}
+
+#[test]
+fn in_place_skips_rewrite_when_no_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("f.txt");
+    std::fs::write(&path, "hello\n").unwrap();
+    let before = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+
+    sub()
+        .arg("-p")
+        .arg("nomatch")
+        .arg("replacement")
+        .arg(&path)
+        .assert()
+        .success();
+
+    let after = std::fs::metadata(&path).unwrap().modified().unwrap();
+    assert_eq!(before, after);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn in_place_follows_symlink_to_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let real = dir.path().join("real.txt");
+    let link = dir.path().join("link.txt");
+    std::fs::write(&real, "foo\n").unwrap();
+    std::os::unix::fs::symlink(&real, &link).unwrap();
+
+    sub()
+        .arg("-p")
+        .arg("foo")
+        .arg("bar")
+        .arg(&link)
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_to_string(&real).unwrap(), "bar\n");
+    assert_eq!(std::fs::read_to_string(&link).unwrap(), "bar\n");
+    assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+}
+
+#[test]
+fn recursive_with_glob_filters_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "foo\n").unwrap();
+    std::fs::write(dir.path().join("b.log"), "foo\n").unwrap();
+
+    sub()
+        .arg("-r")
+        .arg("-p")
+        .arg("--glob")
+        .arg("*.txt")
+        .arg("foo")
+        .arg("bar")
+        .arg(dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+        "bar\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("b.log")).unwrap(),
+        "foo\n"
+    );
+}
+
+#[test]
+fn recursive_without_in_place_or_preview_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "foo\n").unwrap();
+
+    sub()
+        .arg("-r")
+        .arg("foo")
+        .arg("bar")
+        .arg(dir.path())
+        .assert()
+        .failure();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+        "foo\n"
+    );
+}
+
+#[test]
+fn directory_without_recursive_is_rejected_instead_of_panicking() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a.txt"), "foo\n").unwrap();
+
+    sub()
+        .arg("foo")
+        .arg("bar")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .code(1);
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+        "foo\n"
+    );
+}
+
+#[test]
+fn multiline_pattern_spans_newlines() {
+    ReplacementTest::new(r"foo\nbar", "baz")
+        .arg("-M")
+        .for_input("foo\nbar\n")
+        .expect_output("baz\n");
+}
+
+#[test]
+fn multiline_preview_diff_does_not_misalign_following_lines_when_line_count_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("f.txt");
+    std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+    let label = path.to_string_lossy().into_owned();
+
+    sub()
+        .arg("-M")
+        .arg("-n")
+        .arg("two")
+        .arg("X\nY")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(format!("--- {}\n@@ line 2 @@\n-two\n+X\n+Y\n", label));
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "one\ntwo\nthree\nfour\n"
+    );
+}
+
+#[test]
+fn replacement_escape_sequences_are_interpreted() {
+    ReplacementTest::new("a", r"x\ny")
+        .for_input("a\n")
+        .expect_output("x\ny\n");
+}
+
+#[test]
+fn dangling_capture_reference_is_rejected() {
+    sub()
+        .arg("(a)")
+        .arg("$2")
+        .write_stdin("a\n")
+        .assert()
+        .failure()
+        .stderr("[sub error]: Replacement references capture group '2', which does not exist in the pattern\n");
+}
+
+#[test]
+fn escaped_dollar_is_not_treated_as_capture_reference() {
+    ReplacementTest::new("(a)", "x$$3y")
+        .for_input("a\n")
+        .expect_output("x$3y\n");
+}
+
+#[test]
+fn non_utf8_bytes_pass_through_untouched() {
+    let input: Vec<u8> = vec![0xFF, 0xFE, b'f', b'o', b'o', 0x00, b'\n'];
+    let expected: Vec<u8> = vec![0xFF, 0xFE, b'b', b'a', b'r', 0x00, b'\n'];
+    sub()
+        .arg("foo")
+        .arg("bar")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn preview_shows_diff_without_modifying_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("f.txt");
+    std::fs::write(&path, "foo\n").unwrap();
+    let label = path.to_string_lossy().into_owned();
+
+    sub()
+        .arg("-n")
+        .arg("foo")
+        .arg("bar")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(format!("--- {}\n@@ line 1 @@\n-foo\n+bar\n", label));
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "foo\n");
+}